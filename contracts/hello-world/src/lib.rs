@@ -1,6 +1,11 @@
 #![no_std]
 use soroban_sdk::{
-    contract, contractimpl, contracttype, Address, Env, String, Vec, token};
+    contract, contractimpl, contracttype, symbol_short, Address, Env, String, Symbol, Val, Vec, token};
+
+// Minimum delay (seconds) between a proposal's deadline and when a passed
+// action may be executed. Gives members a window to notice a bad outcome
+// before it is enacted on-chain.
+const MIN_ACTION_DELAY: u64 = 86400;
 
 // DAO contract Outline
 
@@ -36,6 +41,27 @@ pub struct TokenConfig {
     admin: Address,
 }
 
+// Structure for DAO-wide governance configuration, set once at initialise
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DaoConfig {
+    voting_quorum_rate: u8, // percentage of total supply required to participate, in (0, 100]
+    min_voting_duration: u64,
+    min_proposal_power: u64,
+    quorum_halving_period: u64, // seconds; quorum requirement halves every period a proposal stays open
+    quorum_floor_rate: u8, // percentage of the base quorum the decayed requirement may never drop below
+    pre_support_time: u64, // seconds before the deadline during which a support snapshot may be taken
+}
+
+// The three ways a member can vote on a proposal
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum VoteChoice {
+    Yes,
+    No,
+    Abstain,
+}
+
 // Structure for a proposal
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -43,11 +69,26 @@ pub struct Proposal {
     id: u64,
     title: String,
     description: String,
+    created_at: u64,
     deadline: u64,
     yes_votes: u64,
     no_votes: u64,
+    abstain_votes: u64,
     creator: Address,
     active: bool,
+    passed: bool,
+    // Whether the proposal is eligible for `execute`: it passed AND held
+    // that same majority at the pre-support snapshot (see `snapshot_support`).
+    // Kept separate from `passed` so a real vote outcome is never misreported
+    // to indexers just because no snapshot was taken.
+    executable: bool,
+    // Optional on-chain action to run once the proposal passes and its
+    // timelock has elapsed. `action_target` is the contract to call.
+    action_target: Option<Address>,
+    action_fn: Option<Symbol>,
+    action_args: Option<Vec<Val>>,
+    executed: bool,
+    execute_after: u64,
 }
 
 // strucutre for a vote
@@ -56,7 +97,33 @@ pub struct Proposal {
 pub struct Vote {
     voter: Address,
     amount: u64,
-    is_yes: bool, // True yes/ False no....
+    choice: VoteChoice,
+}
+
+// Structure for a multi-option ranked-choice (Condorcet) proposal. Unlike a
+// plain Proposal this presents N mutually exclusive options and picks a
+// single winner by pairwise comparison rather than a yes/no majority.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RankedProposal {
+    id: u64,
+    title: String,
+    options: Vec<String>,
+    created_at: u64,
+    deadline: u64,
+    creator: Address,
+    active: bool,
+    winner: Option<u32>,
+}
+
+// structure for a ranked-choice vote: a full or partial ordering of option
+// indices from the voter's most to least preferred
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RankedVote {
+    voter: Address,
+    amount: u64,
+    ranking: Vec<u32>,
 }
 
 //Data key for Storage
@@ -64,10 +131,17 @@ pub struct Vote {
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum DataKey {
     TokenConfig,
+    Config,
     Proposal(u64),
     ProposalCount,
     Vote(u64, Address),
     LockedTokens(Address),
+    Executed(u64),
+    PreSupport(u64),
+    RankedProposal(u64),
+    RankedProposalCount,
+    RankedVote(u64, Address),
+    PairwiseTally(u64),
 }
 
 //Error codes
@@ -82,7 +156,18 @@ pub enum DAOError {
     InsufficientTokens = 6,
     VotingNotClosed = 7,
     InvalidVote = 8,
-    
+    NotPassed = 9,
+    AlreadyExecuted = 10,
+    TimelockNotElapsed = 11,
+    MissingAction = 12,
+    InvalidConfig = 13,
+    VotingTooShort = 14,
+    InsufficientProposalPower = 15,
+    NotInSnapshotWindow = 16,
+    InvalidOptions = 17,
+    InvalidRanking = 18,
+    AlreadySnapshotted = 19,
+
 }
 
 // Implement conversion from DAOError to soroban_sdk::Error
@@ -102,6 +187,15 @@ impl<'a> From<&'a DAOError> for soroban_sdk::Error {
     }
 }
 
+// Build a zero-filled flattened N*N pairwise tally, row-major (index i*n + j)
+fn zero_tally(env: &Env, n: usize) -> Vec<u64> {
+    let mut tally = Vec::new(env);
+    for _ in 0..(n * n) {
+        tally.push_back(0u64);
+    }
+    tally
+}
+
 #[contract]
 pub struct DAOContract;
 
@@ -113,6 +207,12 @@ impl DAOContract {
         admin: Address,
         members: Vec<Address>,
         token_id: Address,
+        voting_quorum_rate: u8,
+        min_voting_duration: u64,
+        min_proposal_power: u64,
+        quorum_halving_period: u64,
+        quorum_floor_rate: u8,
+        pre_support_time: u64,
     ) -> Result<(), DAOError> {
         if env.storage().instance().has(&DataKey::TokenConfig) {
             return Err(DAOError::AlreadyInitialised);
@@ -121,6 +221,16 @@ impl DAOContract {
         // set Admin and authorisation
         admin.require_auth();
 
+        // Quorum rate and floor must be percentages in (0, 100], and the halving period non-zero
+        if voting_quorum_rate == 0
+            || voting_quorum_rate > 100
+            || quorum_floor_rate == 0
+            || quorum_floor_rate > 100
+            || quorum_halving_period == 0
+        {
+            return Err(DAOError::InvalidConfig);
+        }
+
         //calculate total supply of tokens per member
         let total_supply = members.len() as u64 * 100 * 100000;
 
@@ -134,6 +244,17 @@ impl DAOContract {
             .instance()
             .set(&DataKey::TokenConfig, &token_config);
 
+        //Store DAO governance configuration
+        let dao_config = DaoConfig {
+            voting_quorum_rate,
+            min_voting_duration,
+            min_proposal_power,
+            quorum_halving_period,
+            quorum_floor_rate,
+            pre_support_time,
+        };
+        env.storage().instance().set(&DataKey::Config, &dao_config);
+
         //initialse proposal count
         env.storage().instance().set(&DataKey::ProposalCount, &0u64);
 
@@ -179,6 +300,9 @@ impl DAOContract {
         title: String,
         description: String,
         deadline: u64,
+        action_target: Option<Address>,
+        action_fn: Option<Symbol>,
+        action_args: Option<Vec<Val>>,
     ) -> Result<u64, DAOError> {
         //Require creator authorisation
         creator.require_auth();
@@ -189,6 +313,33 @@ impl DAOContract {
             return Err(DAOError::ProposalExpired);
         }
 
+        //Get DAO configuration
+        let dao_config: DaoConfig = env
+            .storage()
+            .instance()
+            .get(&DataKey::Config)
+            .ok_or(DAOError::NotInitialised)?;
+
+        //Enforce minimum voting duration
+        if deadline - current_time < dao_config.min_voting_duration {
+            return Err(DAOError::VotingTooShort);
+        }
+
+        //Get token configuration and ensure the creator holds enough power to propose
+        let token_config: TokenConfig = env
+            .storage()
+            .instance()
+            .get(&DataKey::TokenConfig)
+            .ok_or(DAOError::NotInitialised)?;
+        let token_client = token::Client::new(&env, &token_config.admin);
+        let creator_balance: u64 = token_client
+            .balance(&creator)
+            .try_into()
+            .map_err(|_| DAOError::InvalidVote)?;
+        if creator_balance < dao_config.min_proposal_power {
+            return Err(DAOError::InsufficientProposalPower);
+        }
+
         //Get and increment proposal count
         let proposal_count: u64 = env.storage().instance().get(&DataKey::ProposalCount).unwrap_or(0);
         let new_id = proposal_count + 1;
@@ -198,11 +349,20 @@ impl DAOContract {
             id: new_id,
             title,
             description,
+            created_at: current_time,
             deadline,
             yes_votes: 0,
             no_votes: 0,
+            abstain_votes: 0,
             creator,
             active: true,
+            passed: false,
+            executable: false,
+            action_target,
+            action_fn,
+            action_args,
+            executed: false,
+            execute_after: deadline + MIN_ACTION_DELAY,
         };
 
         //Store proposal and update count
@@ -213,6 +373,12 @@ impl DAOContract {
             .instance()
             .set(&DataKey::ProposalCount, &new_id);
 
+        //Emit a proposal-created event for off-chain indexers
+        env.events().publish(
+            (symbol_short!("proposal"), symbol_short!("created")),
+            (new_id, proposal.creator.clone(), proposal.deadline),
+        );
+
         Ok(new_id)
     }
 
@@ -247,7 +413,7 @@ impl DAOContract {
         env: Env,
         voter: Address,
         proposal_id: u64,
-        is_yes: bool,
+        choice: VoteChoice,
         amount: u64,
     ) -> Result<(), DAOError> {
         voter.require_auth();
@@ -302,17 +468,17 @@ impl DAOContract {
         let vote = Vote {
             voter: voter.clone(),
             amount,
-            is_yes,
+            choice: choice.clone(),
         };
         env.storage()
             .instance()
             .set(&DataKey::Vote(proposal_id, voter.clone()), &vote);
 
         // Update proposal vote counts
-        if is_yes {
-            proposal.yes_votes += amount;
-        } else {
-            proposal.no_votes += amount;
+        match choice {
+            VoteChoice::Yes => proposal.yes_votes += amount,
+            VoteChoice::No => proposal.no_votes += amount,
+            VoteChoice::Abstain => proposal.abstain_votes += amount,
         }
         env.storage()
             .instance()
@@ -325,6 +491,178 @@ impl DAOContract {
             .get(&DataKey::LockedTokens(voter.clone()))
             .unwrap_or(0);
         locked_amount += amount;
+        env.storage()
+            .instance()
+            .set(&DataKey::LockedTokens(voter.clone()), &locked_amount);
+
+        //Emit a vote-cast event for off-chain indexers
+        env.events().publish(
+            (symbol_short!("vote"), symbol_short!("cast")),
+            (proposal_id, voter, vote.choice, amount),
+        );
+
+        Ok(())
+    }
+
+    // Revoke a previously cast vote while the proposal is still open,
+    // returning the locked tokens to the voter
+    pub fn revoke_vote(env: Env, voter: Address, proposal_id: u64) -> Result<(), DAOError> {
+        voter.require_auth();
+
+        //get proposal
+        let mut proposal: Proposal = env
+            .storage()
+            .instance()
+            .get(&DataKey::Proposal(proposal_id))
+            .ok_or(DAOError::InvalidProposal)?;
+
+        //check if proposal is still open
+        let current_time = env.ledger().timestamp();
+        if current_time > proposal.deadline || !proposal.active {
+            return Err(DAOError::ProposalExpired);
+        }
+
+        //get the existing vote
+        let vote: Vote = env
+            .storage()
+            .instance()
+            .get(&DataKey::Vote(proposal_id, voter.clone()))
+            .ok_or(DAOError::InvalidVote)?;
+
+        //Remove the vote from the proposal counters
+        match vote.choice {
+            VoteChoice::Yes => proposal.yes_votes -= vote.amount,
+            VoteChoice::No => proposal.no_votes -= vote.amount,
+            VoteChoice::Abstain => proposal.abstain_votes -= vote.amount,
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::Proposal(proposal_id), &proposal);
+
+        //get token ID and Client
+        let token_config: TokenConfig = env
+            .storage()
+            .instance()
+            .get(&DataKey::TokenConfig)
+            .ok_or(DAOError::NotInitialised)?;
+        let token_client = token::Client::new(&env, &token_config.admin);
+
+        //Return the locked tokens to the voter
+        token_client.transfer(&env.current_contract_address(), &voter, &(vote.amount as i128));
+
+        //Reduce locked token tracking
+        let mut locked_amount: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::LockedTokens(voter.clone()))
+            .unwrap_or(0);
+        locked_amount -= vote.amount;
+        env.storage()
+            .instance()
+            .set(&DataKey::LockedTokens(voter.clone()), &locked_amount);
+
+        //Delete the vote record
+        env.storage()
+            .instance()
+            .remove(&DataKey::Vote(proposal_id, voter));
+
+        Ok(())
+    }
+
+    // Change a previously cast vote while the proposal is still open.
+    // This is revoke-then-vote, atomically: only the delta between the old
+    // and new amount is transferred
+    pub fn change_vote(
+        env: Env,
+        voter: Address,
+        proposal_id: u64,
+        new_choice: VoteChoice,
+        new_amount: u64,
+    ) -> Result<(), DAOError> {
+        voter.require_auth();
+
+        if new_amount <= 0 {
+            return Err(DAOError::InvalidVote);
+        }
+
+        //get proposal
+        let mut proposal: Proposal = env
+            .storage()
+            .instance()
+            .get(&DataKey::Proposal(proposal_id))
+            .ok_or(DAOError::InvalidProposal)?;
+
+        //check if proposal is still open
+        let current_time = env.ledger().timestamp();
+        if current_time > proposal.deadline || !proposal.active {
+            return Err(DAOError::ProposalExpired);
+        }
+
+        //get the existing vote
+        let old_vote: Vote = env
+            .storage()
+            .instance()
+            .get(&DataKey::Vote(proposal_id, voter.clone()))
+            .ok_or(DAOError::InvalidVote)?;
+
+        //Remove the old vote from the proposal counters
+        match old_vote.choice {
+            VoteChoice::Yes => proposal.yes_votes -= old_vote.amount,
+            VoteChoice::No => proposal.no_votes -= old_vote.amount,
+            VoteChoice::Abstain => proposal.abstain_votes -= old_vote.amount,
+        }
+
+        //get token ID and Client
+        let token_config: TokenConfig = env
+            .storage()
+            .instance()
+            .get(&DataKey::TokenConfig)
+            .ok_or(DAOError::NotInitialised)?;
+        let token_client = token::Client::new(&env, &token_config.admin);
+
+        //Transfer only the delta between the old and new locked amount
+        if new_amount > old_vote.amount {
+            let delta = new_amount - old_vote.amount;
+            let available_balance: u64 = token_client
+                .balance(&voter)
+                .try_into()
+                .map_err(|_| DAOError::InvalidVote)?;
+            if available_balance < delta {
+                return Err(DAOError::InsufficientTokens);
+            }
+            token_client.transfer(&voter, &env.current_contract_address(), &(delta as i128));
+        } else if new_amount < old_vote.amount {
+            let delta = old_vote.amount - new_amount;
+            token_client.transfer(&env.current_contract_address(), &voter, &(delta as i128));
+        }
+
+        //Record the new vote
+        let new_vote = Vote {
+            voter: voter.clone(),
+            amount: new_amount,
+            choice: new_choice.clone(),
+        };
+        env.storage()
+            .instance()
+            .set(&DataKey::Vote(proposal_id, voter.clone()), &new_vote);
+
+        //Apply the new vote to the proposal counters
+        match new_choice {
+            VoteChoice::Yes => proposal.yes_votes += new_amount,
+            VoteChoice::No => proposal.no_votes += new_amount,
+            VoteChoice::Abstain => proposal.abstain_votes += new_amount,
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::Proposal(proposal_id), &proposal);
+
+        //Update locked token tracking
+        let mut locked_amount: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::LockedTokens(voter.clone()))
+            .unwrap_or(0);
+        locked_amount = locked_amount - old_vote.amount + new_amount;
         env.storage()
             .instance()
             .set(&DataKey::LockedTokens(voter), &locked_amount);
@@ -332,6 +670,57 @@ impl DAOContract {
         Ok(())
     }
 
+    // Record a pre-support snapshot in the freeze window shortly before the
+    // deadline, so a last-minute vote swing cannot flip an already-decided
+    // outcome into an executable one
+    pub fn snapshot_support(env: Env, proposal_id: u64) -> Result<(), DAOError> {
+        let proposal: Proposal = env
+            .storage()
+            .instance()
+            .get(&DataKey::Proposal(proposal_id))
+            .ok_or(DAOError::InvalidProposal)?;
+
+        // Get DAO configuration
+        let dao_config: DaoConfig = env
+            .storage()
+            .instance()
+            .get(&DataKey::Config)
+            .ok_or(DAOError::NotInitialised)?;
+
+        // Only callable once, and only strictly before the deadline: the window is
+        // [deadline - pre_support_time, deadline). A strict upper bound (rather than
+        // <= deadline) keeps a gap between the snapshot and the moment voting actually
+        // closes, so a last-moment vote cast right at the deadline can never be folded
+        // into the snapshot by also racing a `snapshot_support` call at that instant.
+        // Saturating the lower bound so a window wider than the deadline itself just
+        // opens at time zero instead of underflowing.
+        if env
+            .storage()
+            .instance()
+            .has(&DataKey::PreSupport(proposal_id))
+        {
+            return Err(DAOError::AlreadySnapshotted);
+        }
+        let current_time = env.ledger().timestamp();
+        let window_start = proposal.deadline.saturating_sub(dao_config.pre_support_time);
+        if current_time < window_start || current_time >= proposal.deadline {
+            return Err(DAOError::NotInSnapshotWindow);
+        }
+
+        let supported = proposal.yes_votes > proposal.no_votes;
+        env.storage()
+            .instance()
+            .set(&DataKey::PreSupport(proposal_id), &supported);
+
+        //Emit a snapshot event so watchers know the freeze took effect
+        env.events().publish(
+            (symbol_short!("proposal"), symbol_short!("snapshot")),
+            (proposal_id, supported),
+        );
+
+        Ok(())
+    }
+
     // Tally proposal outcome and close voting
     pub fn tally_proposal(env: Env, proposal_id: u64) -> Result<bool, DAOError> {
         let mut proposal: Proposal = env
@@ -354,36 +743,173 @@ impl DAOContract {
             .ok_or(DAOError::NotInitialised)?;
         let total_supply = token_config.total_supply;
 
-        // Calculate total votes
-        let total_votes = proposal.yes_votes + proposal.no_votes;
+        // Get DAO configuration
+        let dao_config: DaoConfig = env
+            .storage()
+            .instance()
+            .get(&DataKey::Config)
+            .ok_or(DAOError::NotInitialised)?;
+
+        // Calculate total participation (yes, no and abstain all count)
+        let total_votes = proposal.yes_votes + proposal.no_votes + proposal.abstain_votes;
 
-        // Check quorum: total votes > 51% of total supply
-        let quorum_threshold = total_supply * 51 / 100;
+        // Check quorum: participation must exceed the quorum rate, decayed over
+        // time so long-running proposals eventually become passable. Halves
+        // every `quorum_halving_period`, floored at `quorum_floor_rate`.
+        let base_threshold = total_supply * dao_config.voting_quorum_rate as u64 / 100;
+        let floor_threshold = base_threshold * dao_config.quorum_floor_rate as u64 / 100;
+        let elapsed = proposal.deadline - proposal.created_at;
+        let halvings = (elapsed / dao_config.quorum_halving_period).min(63);
+        let decayed_threshold = (base_threshold >> halvings).max(floor_threshold);
+        let quorum_threshold = decayed_threshold;
         if total_votes <= quorum_threshold {
             proposal.active = false;
             env.storage()
                 .instance()
                 .set(&DataKey::Proposal(proposal_id), &proposal);
+
+            //Emit a proposal-tallied event for off-chain indexers
+            env.events().publish(
+                (symbol_short!("proposal"), symbol_short!("tallied")),
+                (proposal_id, false, proposal.yes_votes, proposal.no_votes, total_supply),
+            );
+
             return Ok(false);
         }
 
-        // Determine outcome: yes votes > no votes
+        // Determine outcome: yes votes > no votes. This reflects the actual
+        // vote result and is reported as-is, regardless of the snapshot.
         let passed = proposal.yes_votes > proposal.no_votes;
 
-        // Close proposal
+        // Separately, the proposal is only executable if it also held that
+        // same majority at the pre-support snapshot. No snapshot means it
+        // was never confirmed executable. This only gates `execute`, not
+        // the reported verdict.
+        let pre_supported: bool = env
+            .storage()
+            .instance()
+            .get(&DataKey::PreSupport(proposal_id))
+            .unwrap_or(false);
+
+        // Close proposal and record the outcome. Passing here only marks
+        // the verdict - running the attached action is a separate,
+        // timelocked step via `execute`.
         proposal.active = false;
+        proposal.passed = passed;
+        proposal.executable = passed && pre_supported;
         env.storage()
             .instance()
             .set(&DataKey::Proposal(proposal_id), &proposal);
 
+        //Emit a proposal-tallied event for off-chain indexers
+        env.events().publish(
+            (symbol_short!("proposal"), symbol_short!("tallied")),
+            (proposal_id, passed, proposal.yes_votes, proposal.no_votes, total_supply),
+        );
+
         Ok(passed)
     }
 
+    // Execute the on-chain action attached to a passed proposal once its
+    // timelock has elapsed. Deliberately permissionless (no require_auth):
+    // the outcome was already decided by the vote, the timelock gives
+    // members a window to react to a bad result, and requiring the original
+    // proposer (or anyone in particular) to be the one to trigger it would
+    // let that party hold a passed proposal hostage by simply not calling
+    // this. Anyone may push an already-decided proposal through.
+    pub fn execute(env: Env, proposal_id: u64) -> Result<Val, DAOError> {
+        //get proposal
+        let mut proposal: Proposal = env
+            .storage()
+            .instance()
+            .get(&DataKey::Proposal(proposal_id))
+            .ok_or(DAOError::InvalidProposal)?;
+
+        // Only a proposal that passed AND held its majority at the
+        // pre-support snapshot can be executed
+        if !proposal.executable {
+            return Err(DAOError::NotPassed);
+        }
+
+        // Refuse to execute twice
+        if proposal.executed || env.storage().instance().has(&DataKey::Executed(proposal_id)) {
+            return Err(DAOError::AlreadyExecuted);
+        }
+
+        // Timelock must have elapsed
+        let current_time = env.ledger().timestamp();
+        if current_time < proposal.execute_after {
+            return Err(DAOError::TimelockNotElapsed);
+        }
+
+        // Proposal must have an attached action to run
+        let target = proposal.action_target.clone().ok_or(DAOError::MissingAction)?;
+        let func = proposal.action_fn.clone().ok_or(DAOError::MissingAction)?;
+        let args = proposal.action_args.clone().unwrap_or(Vec::new(&env));
+
+        //Invoke the stored action
+        let result: Val = env.invoke_contract(&target, &func, args);
+
+        //Mark as executed
+        proposal.executed = true;
+        env.storage()
+            .instance()
+            .set(&DataKey::Proposal(proposal_id), &proposal);
+        env.storage()
+            .instance()
+            .set(&DataKey::Executed(proposal_id), &true);
+
+        Ok(result)
+    }
+
     // Unlock tokens after voting deadline
     pub fn unlock_tokens(env: Env, voter: Address) -> Result<(), DAOError> {
         // Require voter authorization
         voter.require_auth();
 
+        // Refuse to unlock while the voter still has a standing vote on a
+        // proposal that has not been tallied yet (still active, even past
+        // its deadline): otherwise the same balance backing a live vote
+        // could be reclaimed and reused elsewhere while that vote still
+        // counts toward the tally.
+        let proposal_count: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::ProposalCount)
+            .unwrap_or(0);
+        for i in 1..=proposal_count {
+            let proposal: Option<Proposal> = env.storage().instance().get(&DataKey::Proposal(i));
+            if let Some(proposal) = proposal {
+                if proposal.active
+                    && env
+                        .storage()
+                        .instance()
+                        .has(&DataKey::Vote(i, voter.clone()))
+                {
+                    return Err(DAOError::VotingNotClosed);
+                }
+            }
+        }
+        let ranked_proposal_count: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::RankedProposalCount)
+            .unwrap_or(0);
+        for i in 1..=ranked_proposal_count {
+            let ranked_proposal: Option<RankedProposal> =
+                env.storage().instance().get(&DataKey::RankedProposal(i));
+            if let Some(ranked_proposal) = ranked_proposal {
+                if ranked_proposal.active
+                    && env
+                        .storage()
+                        .instance()
+                        .has(&DataKey::RankedVote(i, voter.clone()))
+                {
+                    return Err(DAOError::VotingNotClosed);
+                }
+            }
+        }
+
         // Get locked token amount
         let locked_amount: u64 = env
             .storage()
@@ -412,10 +938,333 @@ impl DAOContract {
         // Clear locked token record
         env.storage()
             .instance()
-            .remove(&DataKey::LockedTokens(voter));
+            .remove(&DataKey::LockedTokens(voter.clone()));
+
+        //Emit a tokens-unlocked event for off-chain indexers
+        env.events().publish(
+            (symbol_short!("tokens"), symbol_short!("unlocked")),
+            (voter, locked_amount),
+        );
+
+        Ok(())
+    }
+
+    // Create a multi-option ranked-choice proposal. `options` are mutually
+    // exclusive candidates (e.g. funding amounts); the winner is decided by
+    // Condorcet pairwise comparison at tally time
+    pub fn create_ranked_proposal(
+        env: Env,
+        creator: Address,
+        title: String,
+        options: Vec<String>,
+        deadline: u64,
+    ) -> Result<u64, DAOError> {
+        //Require creator authorisation
+        creator.require_auth();
+
+        //Validate deadline (must be in the future)
+        let current_time = env.ledger().timestamp();
+        if deadline <= current_time {
+            return Err(DAOError::ProposalExpired);
+        }
+
+        //Need at least two mutually exclusive options to rank
+        if options.len() < 2 {
+            return Err(DAOError::InvalidOptions);
+        }
+
+        //Get DAO configuration
+        let dao_config: DaoConfig = env
+            .storage()
+            .instance()
+            .get(&DataKey::Config)
+            .ok_or(DAOError::NotInitialised)?;
+
+        //Enforce minimum voting duration
+        if deadline - current_time < dao_config.min_voting_duration {
+            return Err(DAOError::VotingTooShort);
+        }
+
+        //Get token configuration and ensure the creator holds enough power to propose
+        let token_config: TokenConfig = env
+            .storage()
+            .instance()
+            .get(&DataKey::TokenConfig)
+            .ok_or(DAOError::NotInitialised)?;
+        let token_client = token::Client::new(&env, &token_config.admin);
+        let creator_balance: u64 = token_client
+            .balance(&creator)
+            .try_into()
+            .map_err(|_| DAOError::InvalidVote)?;
+        if creator_balance < dao_config.min_proposal_power {
+            return Err(DAOError::InsufficientProposalPower);
+        }
+
+        //Get and increment ranked proposal count
+        let proposal_count: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::RankedProposalCount)
+            .unwrap_or(0);
+        let new_id = proposal_count + 1;
+
+        let n = options.len() as usize;
+
+        //create new ranked proposal
+        let proposal = RankedProposal {
+            id: new_id,
+            title,
+            options,
+            created_at: current_time,
+            deadline,
+            creator,
+            active: true,
+            winner: None,
+        };
+
+        //Store proposal, update count and initialise the empty N*N pairwise tally
+        env.storage()
+            .instance()
+            .set(&DataKey::RankedProposal(new_id), &proposal);
+        env.storage()
+            .instance()
+            .set(&DataKey::RankedProposalCount, &new_id);
+        let tally: Vec<u64> = zero_tally(&env, n);
+        env.storage()
+            .instance()
+            .set(&DataKey::PairwiseTally(new_id), &tally);
+
+        //Emit a ranked-proposal-created event for off-chain indexers
+        env.events().publish(
+            (symbol_short!("ranked"), symbol_short!("created")),
+            (new_id, proposal.creator.clone(), proposal.deadline),
+        );
+
+        Ok(new_id)
+    }
+
+    //Get a ranked proposal by ID
+    pub fn get_ranked_proposal(env: Env, proposal_id: u64) -> Result<RankedProposal, DAOError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::RankedProposal(proposal_id))
+            .ok_or(DAOError::InvalidProposal)
+    }
+
+    // List all ranked proposals
+    pub fn list_ranked_proposals(env: Env) -> Result<Vec<RankedProposal>, DAOError> {
+        let proposal_count: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::RankedProposalCount)
+            .unwrap_or(0);
+        let mut proposals = Vec::new(&env);
+
+        for i in 1..=proposal_count {
+            if let Some(proposal) = env.storage().instance().get(&DataKey::RankedProposal(i)) {
+                proposals.push_back(proposal);
+            }
+        }
+        Ok(proposals)
+    }
+
+    // Cast a ranked-choice vote. `ranking` lists option indices best-to-worst
+    // (it may be a partial ordering); every ordered pair (i, j) where option
+    // i is ranked above option j has the voter's token amount added to the
+    // pairwise tally M[i][j]
+    pub fn vote_ranked(
+        env: Env,
+        voter: Address,
+        proposal_id: u64,
+        ranking: Vec<u32>,
+        amount: u64,
+    ) -> Result<(), DAOError> {
+        voter.require_auth();
+
+        if amount <= 0 {
+            return Err(DAOError::InvalidVote);
+        }
+
+        //get ranked proposal
+        let proposal: RankedProposal = env
+            .storage()
+            .instance()
+            .get(&DataKey::RankedProposal(proposal_id))
+            .ok_or(DAOError::InvalidProposal)?;
+
+        //check if proposal is still active
+        let current_time = env.ledger().timestamp();
+        if current_time > proposal.deadline || !proposal.active {
+            return Err(DAOError::ProposalExpired);
+        }
+
+        //Check if voter has already voted
+        if env
+            .storage()
+            .instance()
+            .has(&DataKey::RankedVote(proposal_id, voter.clone()))
+        {
+            return Err(DAOError::AlreadyVoted);
+        }
+
+        //Validate the ranking: every index must be in range and appear at most once
+        let n = proposal.options.len();
+        if ranking.is_empty() || ranking.len() as u32 > n {
+            return Err(DAOError::InvalidRanking);
+        }
+        for (pos, option_idx) in ranking.iter().enumerate() {
+            if option_idx >= n {
+                return Err(DAOError::InvalidRanking);
+            }
+            for other in ranking.iter().skip(pos + 1) {
+                if other == option_idx {
+                    return Err(DAOError::InvalidRanking);
+                }
+            }
+        }
+
+        //get token ID and Client
+        let token_config: TokenConfig = env
+            .storage()
+            .instance()
+            .get(&DataKey::TokenConfig)
+            .ok_or(DAOError::NotInitialised)?;
+        let token_client = token::Client::new(&env, &token_config.admin);
+
+        // Check if voter has sufficient tokens
+        let available_balance: u64 = token_client
+            .balance(&voter)
+            .try_into()
+            .map_err(|_| DAOError::InvalidVote)?;
+        if available_balance < amount {
+            return Err(DAOError::InsufficientTokens);
+        }
+
+        //Lock tokens by transferring to contract
+        token_client.transfer(&voter, &env.current_contract_address(), &(amount as i128));
+
+        //Record the ranking
+        let vote = RankedVote {
+            voter: voter.clone(),
+            amount,
+            ranking: ranking.clone(),
+        };
+        env.storage()
+            .instance()
+            .set(&DataKey::RankedVote(proposal_id, voter.clone()), &vote);
+
+        //Add the voter's token amount to every ordered pair (i, j) they ranked i above j
+        let n = n as usize;
+        let mut tally: Vec<u64> = env
+            .storage()
+            .instance()
+            .get(&DataKey::PairwiseTally(proposal_id))
+            .unwrap_or(zero_tally(&env, n));
+        for (pos, better) in ranking.iter().enumerate() {
+            for worse in ranking.iter().skip(pos + 1) {
+                let idx = (better as usize * n + worse as usize) as u32;
+                let updated = tally.get(idx).unwrap_or(0) + amount;
+                tally.set(idx, updated);
+            }
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::PairwiseTally(proposal_id), &tally);
+
+        //Track locked tokens for voters
+        let mut locked_amount: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::LockedTokens(voter.clone()))
+            .unwrap_or(0);
+        locked_amount += amount;
+        env.storage()
+            .instance()
+            .set(&DataKey::LockedTokens(voter.clone()), &locked_amount);
+
+        //Emit a ranked-vote-cast event for off-chain indexers
+        env.events().publish(
+            (symbol_short!("ranked"), symbol_short!("voted")),
+            (proposal_id, voter, amount),
+        );
 
         Ok(())
     }
+
+    // Tally a ranked proposal: pick the Condorcet winner (the option that
+    // beats every other option head-to-head), falling back to the Minimax
+    // winner (largest worst-case pairwise margin) when the pairwise
+    // preferences form a cycle and no Condorcet winner exists.
+    //
+    // This is a distinct entry point from `tally_proposal` rather than an
+    // overload of it: a ranked proposal returns `(u32, String)` (the winning
+    // option and its label) where a yes/no proposal returns `bool`, and
+    // Soroban contract functions cannot be overloaded on return type. The
+    // two tally functions are kept separate on purpose.
+    pub fn tally_ranked_proposal(env: Env, proposal_id: u64) -> Result<(u32, String), DAOError> {
+        let mut proposal: RankedProposal = env
+            .storage()
+            .instance()
+            .get(&DataKey::RankedProposal(proposal_id))
+            .ok_or(DAOError::InvalidProposal)?;
+
+        // Check if voting period has ended
+        let current_time = env.ledger().timestamp();
+        if current_time <= proposal.deadline {
+            return Err(DAOError::VotingNotClosed);
+        }
+
+        let n = proposal.options.len() as usize;
+        let tally: Vec<u64> = env
+            .storage()
+            .instance()
+            .get(&DataKey::PairwiseTally(proposal_id))
+            .unwrap_or(zero_tally(&env, n));
+        let m = |i: usize, j: usize| -> i128 { tally.get((i * n + j) as u32).unwrap_or(0) as i128 };
+
+        // Look for a Condorcet winner: beats every other option head-to-head
+        let mut condorcet_winner: Option<usize> = None;
+        for w in 0..n {
+            let beats_all = (0..n).all(|j| j == w || m(w, j) > m(j, w));
+            if beats_all {
+                condorcet_winner = Some(w);
+                break;
+            }
+        }
+
+        // No Condorcet winner: fall back to Minimax - the option whose worst
+        // pairwise margin (against its toughest rival) is least bad
+        let winner = condorcet_winner.unwrap_or_else(|| {
+            let mut best = 0usize;
+            let mut best_worst_margin = i128::MIN;
+            for i in 0..n {
+                let worst_margin = (0..n)
+                    .filter(|&j| j != i)
+                    .map(|j| m(i, j) - m(j, i))
+                    .min()
+                    .unwrap_or(0);
+                if worst_margin > best_worst_margin {
+                    best_worst_margin = worst_margin;
+                    best = i;
+                }
+            }
+            best
+        });
+
+        proposal.active = false;
+        proposal.winner = Some(winner as u32);
+        env.storage()
+            .instance()
+            .set(&DataKey::RankedProposal(proposal_id), &proposal);
+
+        //Emit a ranked-proposal-tallied event for off-chain indexers
+        env.events().publish(
+            (symbol_short!("ranked"), symbol_short!("tallied")),
+            (proposal_id, winner as u32),
+        );
+
+        Ok((winner as u32, proposal.options.get(winner as u32).unwrap()))
+    }
 }
 
 mod test;
\ No newline at end of file
@@ -19,7 +19,7 @@ fn setup() -> (DAOContractClient<'static>, Address, Env, Address, Vec<Address>)
     let client = DAOContractClient::new(&env, &contract_id);
 
     env.mock_all_auths();
-    client.initialise(&admin, &members, &token_id);
+    client.initialise(&admin, &members, &token_id, &51u8, &0u64, &0u64, &1_000_000u64, &10u8, &3600u64);
     (client, admin, env, token_id, members)
 }
 
@@ -38,6 +38,9 @@ fn test_proposal_creation() {
         &String::from_str(&env, "Funding Initiative"),
         &String::from_str(&env, "Fund new project"),
         &deadline,
+        &None,
+        &None,
+        &None,
     );
 
     // Verify proposal details
@@ -71,15 +74,18 @@ fn test_voting() {
         &String::from_str(&env, "Funding Initiative"),
         &String::from_str(&env, "Fund new project"),
         &deadline,
+        &None,
+        &None,
+        &None,
     );
 
     // Member 1 votes yes with 50 tokens
     let voter1 = members.get(1).unwrap();
-    client.vote(&voter1, &proposal_id, &true, &50_0000000);
+    client.vote(&voter1, &proposal_id, &VoteChoice::Yes, &50_0000000);
 
     // Member 2 votes no with 30 tokens
     let voter2 = members.get(2).unwrap();
-    client.vote(&voter2, &proposal_id, &false, &30_0000000);
+    client.vote(&voter2, &proposal_id, &VoteChoice::No, &30_0000000);
 
     // Verify proposal vote counts
     let proposal = client.get_proposal(&proposal_id);
@@ -92,11 +98,11 @@ fn test_voting() {
     assert_eq!(token_client.balance(&voter2), 70_0000000); // 100 - 30
 
     // Test duplicate voting
-    let result = client.vote(&voter1, &proposal_id, &true, &10_0000000);
+    let result = client.vote(&voter1, &proposal_id, &VoteChoice::Yes, &10_0000000);
     assert_eq!(result, Err(DAOError::AlreadyVoted));
 
     // Test voting with insufficient tokens
-    let result = client.vote(&voter1, &proposal_id, &true, &100_0000000);
+    let result = client.vote(&voter1, &proposal_id, &VoteChoice::Yes, &100_0000000);
     assert_eq!(result, Err(DAOError::InsufficientTokens));
 }
 
@@ -115,24 +121,32 @@ fn test_tallying_and_unlocking() {
         &String::from_str(&env, "Funding Initiative"),
         &String::from_str(&env, "Fund new project"),
         &deadline,
+        &None,
+        &None,
+        &None,
     );
 
     // Members vote
     let voter1 = members.get(1).unwrap();
     let voter2 = members.get(2).unwrap();
-    client.vote(&voter1, &proposal_id, &true, &60_0000000);
-    client.vote(&voter2, &proposal_id, &false, &40_0000000);
+    client.vote(&voter1, &proposal_id, &VoteChoice::Yes, &60_0000000);
+    client.vote(&voter2, &proposal_id, &VoteChoice::No, &40_0000000);
 
     // Try to tally before deadline
     let result = client.tally_proposal(&proposal_id);
     assert_eq!(result, Err(DAOError::VotingNotClosed));
 
+    // Advance into the pre-support freeze window (strictly before the deadline)
+    // and snapshot the outcome
+    env.ledger().with_mut(|l| l.timestamp += 84600);
+    client.snapshot_support(&proposal_id);
+
     // Advance ledger time past deadline
-    env.ledger().with_mut(|l| l.timestamp += 86400 + 1);
+    env.ledger().with_mut(|l| l.timestamp += 1801);
 
     // Tally proposal
     let passed = client.tally_proposal(&proposal_id);
-    assert_eq!(passed, true); // 60 yes > 40 no, quorum met (100 > 51% of 300)
+    assert_eq!(passed, true); // 60 yes > 40 no, quorum met (1_000_000_000 > 51% of 30_000_000), pre-supported
 
     // Verify proposal is closed
     let proposal = client.get_proposal(&proposal_id);
@@ -151,4 +165,493 @@ fn test_tallying_and_unlocking() {
 
     // Test unlocking with no locked tokens
     client.unlock_tokens(&voter1); // Should not panic
+}
+
+// Test that tokens backing a vote on a still-open proposal cannot be unlocked
+#[test]
+fn test_unlock_tokens_rejects_while_vote_is_active() {
+    let (client, _, env, token_id, members) = setup();
+
+    env.mock_all_auths();
+
+    let creator = members.get(0).unwrap();
+    let deadline = env.ledger().timestamp() + 86400;
+    let proposal_id = client.create_proposal(
+        &creator,
+        &String::from_str(&env, "Funding Initiative"),
+        &String::from_str(&env, "Fund new project"),
+        &deadline,
+        &None,
+        &None,
+        &None,
+    );
+
+    let voter1 = members.get(1).unwrap();
+    client.vote(&voter1, &proposal_id, &VoteChoice::Yes, &60_0000000);
+
+    // The proposal is still open, so the balance backing this vote must stay locked
+    let result = client.unlock_tokens(&voter1);
+    assert_eq!(result, Err(DAOError::VotingNotClosed));
+
+    // Once the proposal is tallied and closed, the same tokens can be unlocked
+    env.ledger().with_mut(|l| l.timestamp += 86400 + 1);
+    client.tally_proposal(&proposal_id);
+    client.unlock_tokens(&voter1);
+
+    let token_client = token::Client::new(&env, &token_id);
+    assert_eq!(token_client.balance(&voter1), 100_0000000);
+}
+
+// Test the execution timelock on a passed proposal
+#[test]
+fn test_execute_timelock() {
+    let (client, _, env, _, members) = setup();
+
+    env.mock_all_auths();
+
+    // Create a proposal with no attached action
+    let creator = members.get(0).unwrap();
+    let deadline = env.ledger().timestamp() + 86400;
+    let proposal_id = client.create_proposal(
+        &creator,
+        &String::from_str(&env, "Funding Initiative"),
+        &String::from_str(&env, "Fund new project"),
+        &deadline,
+        &None,
+        &None,
+        &None,
+    );
+
+    // Members vote
+    let voter1 = members.get(1).unwrap();
+    let voter2 = members.get(2).unwrap();
+    client.vote(&voter1, &proposal_id, &VoteChoice::Yes, &60_0000000);
+    client.vote(&voter2, &proposal_id, &VoteChoice::No, &40_0000000);
+
+    // Executing before the proposal is tallied should fail
+    let result = client.execute(&proposal_id);
+    assert_eq!(result, Err(DAOError::NotPassed));
+
+    // Advance into the pre-support freeze window (strictly before the deadline)
+    // and snapshot the outcome
+    env.ledger().with_mut(|l| l.timestamp += 84600);
+    client.snapshot_support(&proposal_id);
+
+    // Advance past the deadline and tally
+    env.ledger().with_mut(|l| l.timestamp += 1801);
+    let passed = client.tally_proposal(&proposal_id);
+    assert_eq!(passed, true);
+
+    // Timelock has not elapsed yet
+    let result = client.execute(&proposal_id);
+    assert_eq!(result, Err(DAOError::TimelockNotElapsed));
+
+    // Advance past the timelock
+    env.ledger().with_mut(|l| l.timestamp += MIN_ACTION_DELAY);
+
+    // No action was attached to this proposal
+    let result = client.execute(&proposal_id);
+    assert_eq!(result, Err(DAOError::MissingAction));
+}
+
+// Test that skipping the pre-support snapshot blocks execution without
+// misreporting the real vote outcome
+#[test]
+fn test_tally_without_snapshot_reports_real_outcome_but_blocks_execute() {
+    let (client, _, env, _, members) = setup();
+
+    env.mock_all_auths();
+
+    let creator = members.get(0).unwrap();
+    let deadline = env.ledger().timestamp() + 86400;
+    let proposal_id = client.create_proposal(
+        &creator,
+        &String::from_str(&env, "Funding Initiative"),
+        &String::from_str(&env, "Fund new project"),
+        &deadline,
+        &None,
+        &None,
+        &None,
+    );
+
+    // Members vote, but no snapshot_support is ever taken
+    let voter1 = members.get(1).unwrap();
+    let voter2 = members.get(2).unwrap();
+    client.vote(&voter1, &proposal_id, &VoteChoice::Yes, &60_0000000);
+    client.vote(&voter2, &proposal_id, &VoteChoice::No, &40_0000000);
+
+    // Tallying still reports the real vote result: 60 yes > 40 no, quorum met
+    env.ledger().with_mut(|l| l.timestamp += 86400 + 1);
+    let passed = client.tally_proposal(&proposal_id);
+    assert_eq!(passed, true);
+
+    let proposal = client.get_proposal(&proposal_id);
+    assert_eq!(proposal.passed, true);
+    assert_eq!(proposal.executable, false);
+
+    // But execution is blocked since the outcome was never snapshotted
+    let result = client.execute(&proposal_id);
+    assert_eq!(result, Err(DAOError::NotPassed));
+}
+
+// Test that the freeze window can't be raced: the snapshot is one-shot and
+// closes strictly before the deadline, so a last-moment vote can't be
+// folded in by also racing a second snapshot_support call
+#[test]
+fn test_snapshot_support_is_one_shot_and_closes_before_deadline() {
+    let (client, _, env, _, members) = setup();
+
+    env.mock_all_auths();
+
+    let creator = members.get(0).unwrap();
+    let deadline = env.ledger().timestamp() + 86400;
+    let proposal_id = client.create_proposal(
+        &creator,
+        &String::from_str(&env, "Funding Initiative"),
+        &String::from_str(&env, "Fund new project"),
+        &deadline,
+        &None,
+        &None,
+        &None,
+    );
+
+    let voter1 = members.get(1).unwrap();
+    client.vote(&voter1, &proposal_id, &VoteChoice::Yes, &60_0000000);
+
+    // Advance into the freeze window and take the snapshot
+    env.ledger().with_mut(|l| l.timestamp += 84600);
+    client.snapshot_support(&proposal_id);
+
+    // A second snapshot attempt is rejected even though still in the window -
+    // a late vote swing cannot be folded in by re-snapshotting
+    let result = client.snapshot_support(&proposal_id);
+    assert_eq!(result, Err(DAOError::AlreadySnapshotted));
+
+    // A fresh proposal's window is exclusive of the deadline itself: a voter
+    // casting the deciding vote at the exact deadline can't also race a
+    // snapshot_support call at that same instant
+    let deadline2 = env.ledger().timestamp() + 86400;
+    let proposal_id2 = client.create_proposal(
+        &creator,
+        &String::from_str(&env, "Second Initiative"),
+        &String::from_str(&env, "Fund another project"),
+        &deadline2,
+        &None,
+        &None,
+        &None,
+    );
+    client.vote(&voter1, &proposal_id2, &VoteChoice::Yes, &30_0000000);
+    env.ledger().with_mut(|l| l.timestamp += 86400);
+    let result = client.snapshot_support(&proposal_id2);
+    assert_eq!(result, Err(DAOError::NotInSnapshotWindow));
+}
+
+// Test abstain votes and the configured quorum/duration rules
+#[test]
+fn test_abstain_and_config_limits() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    let members = vec![
+        &env,
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    ];
+    let token_id = env.register_stellar_asset_contract(admin.clone());
+    let contract_id = env.register_contract(None, DAOContract);
+    let client = DAOContractClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    client.initialise(&admin, &members, &token_id, &51u8, &3600u64, &0u64, &1_000_000u64, &10u8, &3600u64);
+
+    let creator = members.get(0).unwrap();
+    let deadline = env.ledger().timestamp() + 86400;
+    let proposal_id = client.create_proposal(
+        &creator,
+        &String::from_str(&env, "Funding Initiative"),
+        &String::from_str(&env, "Fund new project"),
+        &deadline,
+        &None,
+        &None,
+        &None,
+    );
+
+    // Member 1 votes yes, member 2 abstains
+    let voter1 = members.get(1).unwrap();
+    let voter2 = members.get(2).unwrap();
+    client.vote(&voter1, &proposal_id, &VoteChoice::Yes, &60_0000000);
+    client.vote(&voter2, &proposal_id, &VoteChoice::Abstain, &40_0000000);
+
+    // Abstains count toward participation but not toward the yes/no decision
+    let proposal = client.get_proposal(&proposal_id);
+    assert_eq!(proposal.yes_votes, 60_0000000);
+    assert_eq!(proposal.abstain_votes, 40_0000000);
+
+    // Advance into the pre-support freeze window (strictly before the deadline)
+    // and snapshot the outcome
+    env.ledger().with_mut(|l| l.timestamp += 84600);
+    client.snapshot_support(&proposal_id);
+
+    // Advance past the deadline and tally - 1_000_000_000 votes met quorum (51% of 30_000_000)
+    env.ledger().with_mut(|l| l.timestamp += 1801);
+    let passed = client.tally_proposal(&proposal_id);
+    assert_eq!(passed, true);
+
+    // A voting window shorter than the configured minimum is rejected
+    let short_deadline = env.ledger().timestamp() + 1;
+    let result = client.create_proposal(
+        &creator,
+        &String::from_str(&env, "Too Fast"),
+        &String::from_str(&env, "Rushed"),
+        &short_deadline,
+        &None,
+        &None,
+        &None,
+    );
+    assert_eq!(result, Err(DAOError::VotingTooShort));
+}
+
+// Test that quorum decays the longer a proposal's voting window is kept open
+#[test]
+fn test_quorum_halving() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    let members = vec![
+        &env,
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    ];
+    let token_id = env.register_stellar_asset_contract(admin.clone());
+    let contract_id = env.register_contract(None, DAOContract);
+    let client = DAOContractClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    // total_supply = 30_000_000; base quorum = 51% = 15_300_000; quorum halves every day
+    client.initialise(&admin, &members, &token_id, &51u8, &0u64, &0u64, &86400u64, &10u8, &3600u64);
+
+    let creator = members.get(0).unwrap();
+    let voter1 = members.get(1).unwrap();
+
+    // A short (1 second) window: no halving applies, the full 51% quorum is required
+    let short_deadline = env.ledger().timestamp() + 1;
+    let short_id = client.create_proposal(
+        &creator,
+        &String::from_str(&env, "Short Window"),
+        &String::from_str(&env, "Closes fast"),
+        &short_deadline,
+        &None,
+        &None,
+        &None,
+    );
+    client.vote(&voter1, &short_id, &VoteChoice::Yes, &2_000_000);
+    env.ledger().with_mut(|l| l.timestamp += 2);
+    let short_passed = client.tally_proposal(&short_id);
+    assert_eq!(short_passed, false); // 2,000,000 <= 15,300,000 base quorum
+
+    // A long (3 day) window: quorum has halved 3 times to 1,912,500
+    let long_deadline = env.ledger().timestamp() + 86400 * 3;
+    let long_id = client.create_proposal(
+        &creator,
+        &String::from_str(&env, "Long Window"),
+        &String::from_str(&env, "Stays open a while"),
+        &long_deadline,
+        &None,
+        &None,
+        &None,
+    );
+    client.vote(&voter1, &long_id, &VoteChoice::Yes, &2_000_000);
+
+    // Advance into the pre-support freeze window and snapshot the outcome
+    env.ledger().with_mut(|l| l.timestamp += 86400 * 3 - 3600);
+    client.snapshot_support(&long_id);
+
+    // Advance past the deadline and tally
+    env.ledger().with_mut(|l| l.timestamp += 3600 + 1);
+    let long_passed = client.tally_proposal(&long_id);
+    assert_eq!(long_passed, true); // 2,000,000 > 1,912,500 decayed quorum, pre-supported
+}
+
+// Test that proposal creation and voting emit events
+#[test]
+fn test_events_emitted() {
+    let (client, _, env, _, members) = setup();
+
+    env.mock_all_auths();
+
+    let creator = members.get(0).unwrap();
+    let deadline = env.ledger().timestamp() + 86400;
+    let proposal_id = client.create_proposal(
+        &creator,
+        &String::from_str(&env, "Funding Initiative"),
+        &String::from_str(&env, "Fund new project"),
+        &deadline,
+        &None,
+        &None,
+        &None,
+    );
+    assert_eq!(env.events().all().len(), 1);
+
+    let voter1 = members.get(1).unwrap();
+    client.vote(&voter1, &proposal_id, &VoteChoice::Yes, &50_0000000);
+    assert_eq!(env.events().all().len(), 2);
+}
+
+// Test revoking and changing a vote before the deadline
+#[test]
+fn test_revoke_and_change_vote() {
+    let (client, _, env, token_id, members) = setup();
+
+    env.mock_all_auths();
+
+    let creator = members.get(0).unwrap();
+    let deadline = env.ledger().timestamp() + 86400;
+    let proposal_id = client.create_proposal(
+        &creator,
+        &String::from_str(&env, "Funding Initiative"),
+        &String::from_str(&env, "Fund new project"),
+        &deadline,
+        &None,
+        &None,
+        &None,
+    );
+
+    let voter1 = members.get(1).unwrap();
+    client.vote(&voter1, &proposal_id, &VoteChoice::Yes, &50_0000000);
+
+    let token_client = token::Client::new(&env, &token_id);
+    assert_eq!(token_client.balance(&voter1), 50_0000000);
+
+    // Revoke returns the locked tokens and clears the vote
+    client.revoke_vote(&voter1, &proposal_id);
+    assert_eq!(token_client.balance(&voter1), 100_0000000);
+    let proposal = client.get_proposal(&proposal_id);
+    assert_eq!(proposal.yes_votes, 0);
+
+    // Vote again, then change it to a smaller abstain amount
+    client.vote(&voter1, &proposal_id, &VoteChoice::Yes, &50_0000000);
+    client.change_vote(&voter1, &proposal_id, &VoteChoice::Abstain, &20_0000000);
+
+    let proposal = client.get_proposal(&proposal_id);
+    assert_eq!(proposal.yes_votes, 0);
+    assert_eq!(proposal.abstain_votes, 20_0000000);
+    assert_eq!(token_client.balance(&voter1), 80_0000000); // 100 - 20 locked
+
+    // Changing a vote to a larger amount locks the extra delta
+    client.change_vote(&voter1, &proposal_id, &VoteChoice::No, &70_0000000);
+    let proposal = client.get_proposal(&proposal_id);
+    assert_eq!(proposal.abstain_votes, 0);
+    assert_eq!(proposal.no_votes, 70_0000000);
+    assert_eq!(token_client.balance(&voter1), 30_0000000); // 100 - 70 locked
+
+    // Revoking past the deadline is not allowed
+    env.ledger().with_mut(|l| l.timestamp += 86400 + 1);
+    let result = client.revoke_vote(&voter1, &proposal_id);
+    assert_eq!(result, Err(DAOError::ProposalExpired));
+}
+
+// Test a ranked (Condorcet) proposal where one option beats every other
+// option head-to-head
+#[test]
+fn test_ranked_proposal_condorcet_winner() {
+    let (client, _, env, token_id, members) = setup();
+
+    env.mock_all_auths();
+
+    let creator = members.get(0).unwrap();
+    let deadline = env.ledger().timestamp() + 86400;
+    let options = vec![
+        &env,
+        String::from_str(&env, "Option A"),
+        String::from_str(&env, "Option B"),
+        String::from_str(&env, "Option C"),
+    ];
+    let proposal_id = client.create_ranked_proposal(&creator, &String::from_str(&env, "Budget Split"), &options, &deadline);
+
+    // Voter1 ranks A > B > C, voter2 ranks B > A > C
+    let voter1 = members.get(1).unwrap();
+    let voter2 = members.get(2).unwrap();
+    client.vote_ranked(&voter1, &proposal_id, &vec![&env, 0u32, 1u32, 2u32], &60_0000000);
+    client.vote_ranked(&voter2, &proposal_id, &vec![&env, 1u32, 0u32, 2u32], &40_0000000);
+
+    // Duplicate voting and out-of-range/duplicate rankings are rejected
+    let result = client.vote_ranked(&voter1, &proposal_id, &vec![&env, 0u32, 1u32], &10_0000000);
+    assert_eq!(result, Err(DAOError::AlreadyVoted));
+    let voter3 = members.get(0).unwrap();
+    let result = client.vote_ranked(&voter3, &proposal_id, &vec![&env, 0u32, 0u32], &10_0000000);
+    assert_eq!(result, Err(DAOError::InvalidRanking));
+    let result = client.vote_ranked(&voter3, &proposal_id, &vec![&env, 3u32], &10_0000000);
+    assert_eq!(result, Err(DAOError::InvalidRanking));
+
+    // Tallying before the deadline is not allowed
+    let result = client.tally_ranked_proposal(&proposal_id);
+    assert_eq!(result, Err(DAOError::VotingNotClosed));
+
+    // Option A beats both B and C head-to-head, so it is the Condorcet winner
+    env.ledger().with_mut(|l| l.timestamp += 86400 + 1);
+    let (winner, winner_title) = client.tally_ranked_proposal(&proposal_id);
+    assert_eq!(winner, 0);
+    assert_eq!(winner_title, String::from_str(&env, "Option A"));
+
+    let proposal = client.get_ranked_proposal(&proposal_id);
+    assert_eq!(proposal.active, false);
+    assert_eq!(proposal.winner, Some(0));
+
+    // Locked tokens are unlocked the same way as a single-choice vote
+    let token_client = token::Client::new(&env, &token_id);
+    client.unlock_tokens(&voter1);
+    assert_eq!(token_client.balance(&voter1), 100_0000000);
+}
+
+// Test that a cyclic (rock-paper-scissors) set of rankings, which has no
+// Condorcet winner, falls back to the Minimax winner instead of panicking
+#[test]
+fn test_ranked_proposal_minimax_fallback() {
+    let (client, _, env, _, members) = setup();
+
+    env.mock_all_auths();
+
+    let creator = members.get(0).unwrap();
+    let deadline = env.ledger().timestamp() + 86400;
+    let options = vec![
+        &env,
+        String::from_str(&env, "Option A"),
+        String::from_str(&env, "Option B"),
+        String::from_str(&env, "Option C"),
+    ];
+    let proposal_id = client.create_ranked_proposal(&creator, &String::from_str(&env, "Three Way Split"), &options, &deadline);
+
+    // A > B > C, B > C > A, C > A > B: a perfect cycle, no Condorcet winner
+    let voter1 = members.get(0).unwrap();
+    let voter2 = members.get(1).unwrap();
+    let voter3 = members.get(2).unwrap();
+    client.vote_ranked(&voter1, &proposal_id, &vec![&env, 0u32, 1u32, 2u32], &10_0000000);
+    client.vote_ranked(&voter2, &proposal_id, &vec![&env, 1u32, 2u32, 0u32], &10_0000000);
+    client.vote_ranked(&voter3, &proposal_id, &vec![&env, 2u32, 0u32, 1u32], &10_0000000);
+
+    env.ledger().with_mut(|l| l.timestamp += 86400 + 1);
+    let (winner, winner_title) = client.tally_ranked_proposal(&proposal_id);
+
+    // The cycle is symmetric, so every option has the same worst-case
+    // margin; the Minimax tiebreak picks the lowest index
+    assert_eq!(winner, 0);
+    assert_eq!(winner_title, String::from_str(&env, "Option A"));
+}
+
+// Test that a ranked proposal needs at least two options
+#[test]
+fn test_ranked_proposal_requires_two_options() {
+    let (client, _, env, _, members) = setup();
+
+    env.mock_all_auths();
+
+    let creator = members.get(0).unwrap();
+    let deadline = env.ledger().timestamp() + 86400;
+    let result = client.create_ranked_proposal(
+        &creator,
+        &String::from_str(&env, "Single Option"),
+        &vec![&env, String::from_str(&env, "Only One")],
+        &deadline,
+    );
+    assert_eq!(result, Err(DAOError::InvalidOptions));
 }
\ No newline at end of file